@@ -18,54 +18,781 @@ use move_vm_runtime::loader;
 use move_vm_runtime::loader::BinaryType::Module;
 use move_vm_runtime::loader::{Function, Loader, ModuleCache, Resolver};
 use move_vm_runtime::native_functions::NativeFunctions;
-use move_vm_types::gas::UnmeteredGasMeter;
+use move_vm_types::gas::{GasMeter, UnmeteredGasMeter};
 use move_vm_types::values;
 use move_vm_types::values::{Locals, Reference, StructRef, Value, ValueImpl, VMValueCast};
+use move_vm_types::views::{TypeView, ValueView};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
 use libafl::state::HasMetadata;
-use move_binary_format::errors::VMResult;
+use move_binary_format::errors::{PartialVMError, PartialVMResult, VMResult};
 use move_binary_format::file_format::Bytecode;
+use move_core_types::gas_algebra::{InternalGas, NumArgs, NumBytes};
 use move_core_types::u256;
+use move_core_types::vm_status::StatusCode;
+use sha3::{Digest, Keccak256};
 
 pub static mut MOVE_COV_MAP: [u8; MAP_SIZE] = [0u8; MAP_SIZE];
 pub static mut MOVE_CMP_MAP: [u128; MAP_SIZE] = [0; MAP_SIZE];
 pub static mut MOVE_READ_MAP: [bool; MAP_SIZE] = [false; MAP_SIZE];
 pub static mut MOVE_WRITE_MAP: [u8; MAP_SIZE] = [0u8; MAP_SIZE];
 pub static mut MOVE_STATE_CHANGED: bool = false;
+
+/// Default per-input instruction budget: a fuzzed input that dispatches more
+/// bytecodes than this without returning is assumed to be stuck in an infinite
+/// (or merely pathological) loop rather than doing useful work.
+pub const DEFAULT_INSTRUCTION_BUDGET: u64 = 10_000_000;
+
+/// A [`GasMeter`] that doesn't model gas cost at all; it only counts dispatched
+/// bytecodes and traps once `budget` is exceeded. This keeps a single fuzzed
+/// input from hanging the whole campaign in a runaway loop. The counter lives
+/// on the meter instance (not a static) so concurrent VMs stay independent.
+pub struct StepBudgetGasMeter {
+    steps: u64,
+    budget: u64,
+}
+
+impl StepBudgetGasMeter {
+    pub fn new(budget: u64) -> Self {
+        Self { steps: 0, budget }
+    }
+
+    /// Returns `true` if the most recent `tick()` pushed us over budget.
+    pub fn is_out_of_budget(&self) -> bool {
+        self.steps > self.budget
+    }
+
+    fn tick(&mut self) -> PartialVMResult<()> {
+        self.steps += 1;
+        if self.steps > self.budget {
+            Err(PartialVMError::new(StatusCode::OUT_OF_GAS)
+                .with_message(format!("step budget of {} instructions exhausted", self.budget)))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl GasMeter for StepBudgetGasMeter {
+    fn balance_internal(&self) -> InternalGas {
+        InternalGas::new(self.budget.saturating_sub(self.steps))
+    }
+
+    fn charge_simple_instr(&mut self, _instr: move_vm_types::gas::SimpleInstruction) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_pop(&mut self, _popped_val: impl ValueView) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_call(
+        &mut self,
+        _module_id: &ModuleId,
+        _func_name: &str,
+        _args: impl ExactSizeIterator<Item = impl ValueView>,
+        _num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_call_generic(
+        &mut self,
+        _module_id: &ModuleId,
+        _func_name: &str,
+        _ty_args: impl ExactSizeIterator<Item = impl TypeView>,
+        _args: impl ExactSizeIterator<Item = impl ValueView>,
+        _num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_ld_const(&mut self, _size: NumBytes) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_ld_const_after_deserialization(&mut self, _val: impl ValueView) -> PartialVMResult<()> {
+        Ok(())
+    }
+
+    fn charge_copy_loc(&mut self, _val: impl ValueView) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_move_loc(&mut self, _val: impl ValueView) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_store_loc(&mut self, _val: impl ValueView) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_pack(&mut self, _is_generic: bool, _args: impl ExactSizeIterator<Item = impl ValueView>) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_unpack(&mut self, _is_generic: bool, _args: impl ExactSizeIterator<Item = impl ValueView>) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_read_ref(&mut self, _val: impl ValueView) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_write_ref(&mut self, _val: impl ValueView) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_eq(&mut self, _lhs: impl ValueView, _rhs: impl ValueView) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_neq(&mut self, _lhs: impl ValueView, _rhs: impl ValueView) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_borrow_global(
+        &mut self,
+        _is_mut: bool,
+        _is_generic: bool,
+        _ty: impl TypeView,
+        _is_success: bool,
+    ) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_exists(&mut self, _is_generic: bool, _ty: impl TypeView, _exists: bool) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_move_from(
+        &mut self,
+        _is_generic: bool,
+        _ty: impl TypeView,
+        _val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_move_to(
+        &mut self,
+        _is_generic: bool,
+        _ty: impl TypeView,
+        _val: impl ValueView,
+        _is_success: bool,
+    ) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_native_function(
+        &mut self,
+        _amount: InternalGas,
+        _ret_vals: Option<impl ExactSizeIterator<Item = impl ValueView>>,
+    ) -> PartialVMResult<()> {
+        self.tick()
+    }
+
+    fn charge_native_function_before_execution(
+        &mut self,
+        _ty_args: impl ExactSizeIterator<Item = impl TypeView>,
+        _args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        Ok(())
+    }
+
+    fn charge_drop_frame(&mut self, _locals: impl Iterator<Item = impl ValueView>) -> PartialVMResult<()> {
+        Ok(())
+    }
+}
+
+/// Why a Move execution stopped without returning normally. Oracles care about
+/// *which* of these happened, not just that `reverted` is `true`: an explicit
+/// `Aborted` with a user sub-status is how Move invariants are supposed to be
+/// signalled, while an arithmetic trap or a resource-existence violation is
+/// much more likely to be an actual bug.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MoveAbortReason {
+    /// `ABORT` with the Move-level abort code the module raised.
+    Aborted { abort_code: u64 },
+    /// Overflow, underflow, or division/modulo by zero in an arithmetic op.
+    ArithmeticError,
+    /// `MoveTo` targeting an address that already holds the resource.
+    ResourceAlreadyExists,
+    /// `MoveFrom`/`BorrowGlobal` targeting an address missing the resource.
+    ResourceDoesNotExist,
+    /// Out-of-bounds access (vector index, local slot, etc.) or a type-safety
+    /// violation caught by the interpreter's runtime checks.
+    OutOfBoundsOrTypeError,
+    /// Anything else the loader/interpreter rejected.
+    Other(StatusCode),
+}
+
+/// Classify a failing [`VMResult`] error into a [`MoveAbortReason`] so callers
+/// can treat "the module said no" differently from "the VM hit a genuine trap".
+fn classify_abort(err: &move_binary_format::errors::VMError) -> MoveAbortReason {
+    match err.major_status() {
+        StatusCode::ABORTED => MoveAbortReason::Aborted {
+            abort_code: err.sub_status().unwrap_or(0),
+        },
+        StatusCode::ARITHMETIC_ERROR => MoveAbortReason::ArithmeticError,
+        StatusCode::RESOURCE_ALREADY_EXISTS => MoveAbortReason::ResourceAlreadyExists,
+        StatusCode::MISSING_DATA => MoveAbortReason::ResourceDoesNotExist,
+        StatusCode::INDEX_OUT_OF_BOUNDS | StatusCode::TYPE_MISMATCH => {
+            MoveAbortReason::OutOfBoundsOrTypeError
+        }
+        other => MoveAbortReason::Other(other),
+    }
+}
+
+/// A dense mapping from bytecode offset to basic-block id for a single
+/// function, used to turn raw PC hashing into real edge coverage. Blocks
+/// start at pc 0, at every branch target, and right after any
+/// `Branch`/`BrTrue`/`BrFalse`/`Ret`/`Call`/`CallGeneric`.
+pub struct BlockMap {
+    pc_to_block: Vec<u16>,
+    // Stable per-(module, function) salt mixed into the edge hash in
+    // `on_step` so two different functions with structurally-identical
+    // block graphs (e.g. both just "block 0 -> block 1") don't collide into
+    // the same `MOVE_COV_MAP` buckets. See `BlockMap::build`.
+    salt: u32,
+}
+
+impl BlockMap {
+    pub fn build(code: &[Bytecode], salt: u32) -> Self {
+        let mut block_starts: std::collections::BTreeSet<u16> = std::collections::BTreeSet::new();
+        block_starts.insert(0);
+        for (pc, instr) in code.iter().enumerate() {
+            let pc = pc as u16;
+            match instr {
+                Bytecode::Branch(offset) => {
+                    block_starts.insert(*offset);
+                    block_starts.insert(pc + 1);
+                }
+                Bytecode::BrTrue(offset) | Bytecode::BrFalse(offset) => {
+                    block_starts.insert(*offset);
+                    block_starts.insert(pc + 1);
+                }
+                Bytecode::Ret | Bytecode::Call(_) | Bytecode::CallGeneric(_) => {
+                    block_starts.insert(pc + 1);
+                }
+                _ => {}
+            }
+        }
+
+        let starts: Vec<u16> = block_starts
+            .into_iter()
+            .filter(|&p| (p as usize) < code.len())
+            .collect();
+        let mut pc_to_block = vec![0u16; code.len()];
+        let mut block_id: u16 = 0;
+        let mut next_start = 1usize;
+        for (pc, slot) in pc_to_block.iter_mut().enumerate() {
+            if next_start < starts.len() && starts[next_start] as usize == pc {
+                block_id += 1;
+                next_start += 1;
+            }
+            *slot = block_id;
+        }
+
+        Self { pc_to_block, salt }
+    }
+
+    pub fn block_of(&self, pc: u16) -> u16 {
+        self.pc_to_block.get(pc as usize).copied().unwrap_or(0)
+    }
+
+    pub fn salt(&self) -> u32 {
+        self.salt
+    }
+}
+
+/// Derives `BlockMap::salt` from the owning module/function identity, so the
+/// edge hash's input domain isn't just the (tiny, per-function-relative)
+/// block ids -- which would otherwise collide across every function sharing
+/// a shape like "block 0 -> block 1".
+fn function_salt(module_id: &ModuleId, function: &Identifier) -> u32 {
+    let mut hasher = Keccak256::new();
+    hasher.update(format!("{:?}", module_id).as_bytes());
+    hasher.update(function.as_bytes());
+    let digest = hasher.finalize();
+    u32::from_le_bytes(digest[0..4].try_into().unwrap())
+}
+
+/// Cache of precomputed [`BlockMap`]s keyed by the owning module and function,
+/// populated once at `deploy` time and shared (via `Arc`) with every execution
+/// of that function.
+static mut BLOCK_MAP_CACHE: Option<HashMap<(ModuleId, Identifier), Arc<BlockMap>>> = None;
+
+fn block_map_cache() -> &'static mut HashMap<(ModuleId, Identifier), Arc<BlockMap>> {
+    unsafe {
+        if BLOCK_MAP_CACHE.is_none() {
+            BLOCK_MAP_CACHE = Some(HashMap::new());
+        }
+        BLOCK_MAP_CACHE.as_mut().unwrap()
+    }
+}
+
+thread_local! {
+    // `(prev_block >> 1)` from the AFL-style edge hash, reset at call-frame
+    // boundaries so coverage from the caller's blocks doesn't bleed into the
+    // callee's (and vice versa on return).
+    static PREV_BLOCK: std::cell::Cell<u16> = std::cell::Cell::new(0);
+    // Set whenever the current execution touches a (module, function, pc)
+    // edge the campaign-wide bloom filter hasn't seen before; read back by
+    // `MoveVM::has_new_coverage` right after `execute` returns.
+    static FOUND_NEW_COVERAGE: std::cell::Cell<bool> = std::cell::Cell::new(false);
+}
+
+/// Number of bits in the coverage bloom filter. Small and lossy on purpose:
+/// it only needs to cheaply tell "definitely not new" from "maybe new",
+/// false positives just cost the occasional dropped input.
+pub const COVERAGE_BLOOM_BITS: usize = 2048;
+pub const COVERAGE_BLOOM_HASHES: usize = 3;
+
+/// A fixed-size bloom filter over `(module, function, pc)` edges, used to
+/// cheaply skip inputs whose whole execution trace has already been seen in
+/// the corpus. Bitwise-mergeable so parallel/distributed fuzzers can pool
+/// coverage without resolving individual edges against each other.
+#[derive(Clone)]
+pub struct CoverageBloomFilter {
+    bits: [u8; COVERAGE_BLOOM_BITS / 8],
+}
+
+impl CoverageBloomFilter {
+    pub fn new() -> Self {
+        Self {
+            bits: [0u8; COVERAGE_BLOOM_BITS / 8],
+        }
+    }
+
+    fn bit_indices(module_id: &ModuleId, function: &str, pc: u16) -> [usize; COVERAGE_BLOOM_HASHES] {
+        let mut hasher = Keccak256::new();
+        hasher.update(format!("{:?}", module_id).as_bytes());
+        hasher.update(function.as_bytes());
+        hasher.update(pc.to_le_bytes());
+        let digest = hasher.finalize();
+
+        let mut indices = [0usize; COVERAGE_BLOOM_HASHES];
+        for (i, chunk) in digest.chunks(4).take(COVERAGE_BLOOM_HASHES).enumerate() {
+            let word = u32::from_le_bytes(chunk.try_into().unwrap());
+            indices[i] = (word as usize) % COVERAGE_BLOOM_BITS;
+        }
+        indices
+    }
+
+    fn get_bit(&self, idx: usize) -> bool {
+        (self.bits[idx / 8] >> (idx % 8)) & 1 == 1
+    }
+
+    fn set_bit(&mut self, idx: usize) {
+        self.bits[idx / 8] |= 1 << (idx % 8);
+    }
+
+    /// Record a covered edge. Returns `true` if any of its `k` bits were
+    /// previously unset, i.e. this edge is new (modulo false positives).
+    pub fn insert(&mut self, module_id: &ModuleId, function: &str, pc: u16) -> bool {
+        let mut is_new = false;
+        for idx in Self::bit_indices(module_id, function, pc) {
+            if !self.get_bit(idx) {
+                is_new = true;
+                self.set_bit(idx);
+            }
+        }
+        is_new
+    }
+
+    /// `true` iff every edge in `trace` already has all its bits set.
+    pub fn contains_all(&self, trace: &[(ModuleId, String, u16)]) -> bool {
+        trace.iter().all(|(module_id, function, pc)| {
+            Self::bit_indices(module_id, function, *pc)
+                .iter()
+                .all(|&idx| self.get_bit(idx))
+        })
+    }
+
+    /// Reset for a new fuzzing campaign.
+    pub fn reset(&mut self) {
+        self.bits = [0u8; COVERAGE_BLOOM_BITS / 8];
+    }
+
+    /// Pool coverage from another worker's filter (e.g. in a distributed
+    /// fuzzer) by OR-ing the bitsets together.
+    pub fn merge(&mut self, other: &CoverageBloomFilter) {
+        for (mine, theirs) in self.bits.iter_mut().zip(other.bits.iter()) {
+            *mine |= *theirs;
+        }
+    }
+}
+
+static mut COVERAGE_BLOOM: Option<CoverageBloomFilter> = None;
+
+fn coverage_bloom() -> &'static mut CoverageBloomFilter {
+    unsafe {
+        if COVERAGE_BLOOM.is_none() {
+            COVERAGE_BLOOM = Some(CoverageBloomFilter::new());
+        }
+        COVERAGE_BLOOM.as_mut().unwrap()
+    }
+}
+
+/// A host/intrinsic implementation registered for a `native fun`. Unlike the
+/// real Move VM's native dispatch (which threads gas metering and type
+/// arguments through a `NativeContext`), `execute` resolves and invokes these
+/// directly, so the signature is kept to what a fuzzing harness actually
+/// needs: the popped argument values in, the return values out.
+pub type NativeFunctionImpl = Arc<dyn Fn(Vec<Value>) -> VMResult<Vec<Value>> + Send + Sync>;
+
+/// Host implementations for the handful of Move stdlib natives a
+/// stdlib-dependent fuzzed module is most likely to actually call:
+/// `vector`, `signer::borrow_address`, and the `hash`/`bcs` primitives
+/// bytes-oriented contracts lean on. Registered by default in
+/// `MoveVM::register_default_natives`.
+///
+/// Deliberately narrow: `vector` only models the `vector<u8>` container
+/// (the common case for hashing/serialization), since the harness's native
+/// calling convention -- see `NativeFunctionImpl` -- doesn't carry type
+/// arguments to dispatch a truly generic element type on.
+mod default_natives {
+    use super::*;
+    use sha2::Sha256;
+    use sha3::Sha3_256;
+
+    fn native_error(status: StatusCode, msg: String) -> move_binary_format::errors::VMError {
+        PartialVMError::new(status)
+            .with_message(msg)
+            .finish(move_core_types::vm_status::Location::Undefined)
+    }
+
+    fn pvm_to_vm<T>(r: PartialVMResult<T>) -> VMResult<T> {
+        r.map_err(|e| e.finish(move_core_types::vm_status::Location::Undefined))
+    }
+
+    fn type_mismatch(expected: &str, got: &ValueImpl) -> move_binary_format::errors::VMError {
+        native_error(StatusCode::TYPE_MISMATCH, format!("expected {}, got {:?}", expected, got))
+    }
+
+    fn vec_u8_rc(value: &Value) -> VMResult<std::rc::Rc<std::cell::RefCell<Vec<u8>>>> {
+        match &value.0 {
+            ValueImpl::Container(values::Container::VecU8(rc)) => Ok(rc.clone()),
+            ValueImpl::ContainerRef(values::ContainerRef::Local(values::Container::VecU8(rc))) => Ok(rc.clone()),
+            other => Err(type_mismatch("vector<u8>", other)),
+        }
+    }
+
+    fn u64_of(value: &Value) -> VMResult<u64> {
+        match &value.0 {
+            ValueImpl::U64(v) => Ok(*v),
+            other => Err(type_mismatch("u64", other)),
+        }
+    }
+
+    pub fn vector_empty(_args: Vec<Value>) -> VMResult<Vec<Value>> {
+        Ok(vec![Value::vector_u8(vec![])])
+    }
+
+    pub fn vector_length(args: Vec<Value>) -> VMResult<Vec<Value>> {
+        let rc = vec_u8_rc(&args[0])?;
+        Ok(vec![Value::u64(rc.borrow().len() as u64)])
+    }
+
+    pub fn vector_push_back(args: Vec<Value>) -> VMResult<Vec<Value>> {
+        let rc = vec_u8_rc(&args[0])?;
+        let byte = match &args[1].0 {
+            ValueImpl::U8(v) => *v,
+            other => return Err(type_mismatch("u8", other)),
+        };
+        rc.borrow_mut().push(byte);
+        Ok(vec![])
+    }
+
+    pub fn vector_pop_back(args: Vec<Value>) -> VMResult<Vec<Value>> {
+        let rc = vec_u8_rc(&args[0])?;
+        let popped = rc
+            .borrow_mut()
+            .pop()
+            .ok_or_else(|| native_error(StatusCode::INDEX_OUT_OF_BOUNDS, "pop_back on empty vector".to_string()))?;
+        Ok(vec![Value::u8(popped)])
+    }
+
+    pub fn vector_borrow(args: Vec<Value>) -> VMResult<Vec<Value>> {
+        let rc = vec_u8_rc(&args[0])?;
+        let idx = u64_of(&args[1])? as usize;
+        let byte = *rc.borrow().get(idx).ok_or_else(|| {
+            native_error(StatusCode::INDEX_OUT_OF_BOUNDS, format!("index {} out of bounds", idx))
+        })?;
+        Ok(vec![Value::u8(byte)])
+    }
+
+    pub fn vector_swap(args: Vec<Value>) -> VMResult<Vec<Value>> {
+        let rc = vec_u8_rc(&args[0])?;
+        let i = u64_of(&args[1])? as usize;
+        let j = u64_of(&args[2])? as usize;
+        let mut v = rc.borrow_mut();
+        if i >= v.len() || j >= v.len() {
+            return Err(native_error(StatusCode::INDEX_OUT_OF_BOUNDS, "swap index out of bounds".to_string()));
+        }
+        v.swap(i, j);
+        Ok(vec![])
+    }
+
+    pub fn vector_destroy_empty(args: Vec<Value>) -> VMResult<Vec<Value>> {
+        let rc = vec_u8_rc(&args[0])?;
+        if !rc.borrow().is_empty() {
+            return Err(native_error(StatusCode::ABORTED, "destroy_empty on non-empty vector".to_string()));
+        }
+        Ok(vec![])
+    }
+
+    pub fn signer_borrow_address(args: Vec<Value>) -> VMResult<Vec<Value>> {
+        let addr_struct: StructRef = pvm_to_vm(args[0].clone().cast())?;
+        let field0 = pvm_to_vm(addr_struct.borrow_field(0))?;
+        let reference: Reference = pvm_to_vm(field0.value_as())?;
+        let deref = pvm_to_vm(reference.read_ref())?;
+        let addr: AccountAddress = pvm_to_vm(deref.value_as())?;
+        Ok(vec![Value(ValueImpl::Address(addr))])
+    }
+
+    pub fn hash_sha2_256(args: Vec<Value>) -> VMResult<Vec<Value>> {
+        let rc = vec_u8_rc(&args[0])?;
+        let digest = Sha256::digest(rc.borrow().as_slice());
+        Ok(vec![Value::vector_u8(digest.to_vec())])
+    }
+
+    pub fn hash_sha3_256(args: Vec<Value>) -> VMResult<Vec<Value>> {
+        let rc = vec_u8_rc(&args[0])?;
+        let digest = Sha3_256::digest(rc.borrow().as_slice());
+        Ok(vec![Value::vector_u8(digest.to_vec())])
+    }
+
+    fn write_uleb128(out: &mut Vec<u8>, mut value: u64) {
+        loop {
+            let mut byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value != 0 {
+                byte |= 0x80;
+            }
+            out.push(byte);
+            if value == 0 {
+                break;
+            }
+        }
+    }
+
+    /// Serializes the scalar/bytes `ValueImpl` variants a fuzzed input
+    /// actually produces in BCS's little-endian, ULEB128-length-prefixed
+    /// wire format. Unlike `encode_value` (this module's own corpus
+    /// format), this has to match the real `bcs` crate byte-for-byte, since
+    /// a fuzzed contract may compare it against an on-chain digest.
+    fn bcs_serialize(value: &ValueImpl) -> VMResult<Vec<u8>> {
+        let mut out = Vec::new();
+        match value {
+            ValueImpl::Bool(v) => out.push(*v as u8),
+            ValueImpl::U8(v) => out.push(*v),
+            ValueImpl::U16(v) => out.extend_from_slice(&v.to_le_bytes()),
+            ValueImpl::U32(v) => out.extend_from_slice(&v.to_le_bytes()),
+            ValueImpl::U64(v) => out.extend_from_slice(&v.to_le_bytes()),
+            ValueImpl::U128(v) => out.extend_from_slice(&v.to_le_bytes()),
+            ValueImpl::U256(v) => out.extend_from_slice(&v.to_le_bytes()),
+            ValueImpl::Address(addr) => out.extend_from_slice(addr.as_slice()),
+            ValueImpl::Container(values::Container::VecU8(rc)) => {
+                let bytes = rc.borrow();
+                write_uleb128(&mut out, bytes.len() as u64);
+                out.extend_from_slice(&bytes);
+            }
+            other => return Err(type_mismatch("a bcs-serializable scalar or vector<u8>", other)),
+        }
+        Ok(out)
+    }
+
+    pub fn bcs_to_bytes(args: Vec<Value>) -> VMResult<Vec<Value>> {
+        let bytes = bcs_serialize(&args[0].0)?;
+        Ok(vec![Value::vector_u8(bytes)])
+    }
+}
+
 pub struct MoveVM<I, S> {
     // for comm with move_vm
     pub functions: HashMap<ModuleId, HashMap<Identifier, Arc<Function>>>,
     pub loader: Loader,
+    // natives dispatched directly by `execute`; see `register_native`.
+    natives: HashMap<(ModuleId, Identifier), NativeFunctionImpl>,
+    // See `MoveVMTracer::record_trace`: off by default, only turned on when
+    // minimizing/reproducing a saved testcase.
+    replay_trace: bool,
     _phantom: std::marker::PhantomData<(I, S)>,
 }
 
 impl<I, S> MoveVM<I, S> {
     pub fn new() -> Self {
-        let functions = HashMap::new();
-        Self {
-            functions,
+        let natives = HashMap::new();
+        let mut vm = Self {
+            functions: HashMap::new(),
             loader: Loader::new(NativeFunctions::new(vec![]).unwrap(), Default::default()),
+            natives,
+            replay_trace: false,
             _phantom: Default::default(),
+        };
+        vm.register_default_natives();
+        vm
+    }
+
+    /// Toggle recording a full replayable instruction trace during
+    /// `execute`. Keep this off for throughput fuzzing; enable it only when
+    /// minimizing or reproducing a saved crashing testcase.
+    pub fn set_replay_trace(&mut self, enabled: bool) {
+        self.replay_trace = enabled;
+    }
+
+    /// Register the implementation for a `native fun` so `execute` can
+    /// dispatch calls to it (a call to an unregistered native fails with
+    /// `MISSING_DEPENDENCY`). Rebuilds `self.loader` so the declared native
+    /// resolves during module linking at `deploy` time.
+    pub fn register_native(&mut self, module: ModuleId, name: Identifier, implementation: NativeFunctionImpl) {
+        self.natives.insert((module, name), implementation);
+        self.loader = Loader::new(self.get_natives(), Default::default());
+    }
+
+    /// Registers host implementations for the small slice of Move stdlib
+    /// natives (`vector`, `signer`, `hash`, `bcs`) a fuzzed module is likely
+    /// to actually import, so such a module links at `deploy` and its native
+    /// calls resolve in `execute` instead of failing with "no native
+    /// implementation registered". See `default_natives` for what's
+    /// actually modelled.
+    pub fn register_default_natives(&mut self) {
+        let stdlib = AccountAddress::from_hex_literal("0x1").expect("0x1 is a valid address literal");
+        macro_rules! stdlib_native {
+            ($module:expr, $name:expr, $imp:expr) => {
+                self.register_native(
+                    ModuleId::new(stdlib, Identifier::new($module).unwrap()),
+                    Identifier::new($name).unwrap(),
+                    Arc::new($imp),
+                );
+            };
         }
+
+        stdlib_native!("vector", "empty", default_natives::vector_empty);
+        stdlib_native!("vector", "length", default_natives::vector_length);
+        stdlib_native!("vector", "push_back", default_natives::vector_push_back);
+        stdlib_native!("vector", "pop_back", default_natives::vector_pop_back);
+        stdlib_native!("vector", "borrow", default_natives::vector_borrow);
+        stdlib_native!("vector", "swap", default_natives::vector_swap);
+        stdlib_native!("vector", "destroy_empty", default_natives::vector_destroy_empty);
+        stdlib_native!("signer", "borrow_address", default_natives::signer_borrow_address);
+        stdlib_native!("hash", "sha2_256", default_natives::hash_sha2_256);
+        stdlib_native!("hash", "sha3_256", default_natives::hash_sha3_256);
+        stdlib_native!("bcs", "to_bytes", default_natives::bcs_to_bytes);
+    }
+
+    /// `true` if the most recent `execute` touched at least one `(module,
+    /// function, pc)` edge the campaign-wide coverage bloom filter hadn't
+    /// seen before. Call this right after `execute` to decide whether the
+    /// input is worth keeping.
+    pub fn has_new_coverage(&self) -> bool {
+        FOUND_NEW_COVERAGE.with(|f| f.get())
     }
 
+    /// Builds a [`NativeFunctions`] table covering every module/function pair
+    /// we have a registered implementation for. The closures it wraps are
+    /// never actually called -- `execute` dispatches through `self.natives`
+    /// directly -- they only need to exist so the loader's linking pass
+    /// resolves `native fun` declarations instead of failing to find them.
     pub fn get_natives(&self) -> NativeFunctions {
-        NativeFunctions {
-            0: Default::default(),
+        let entries = self.natives.keys().map(|(module_id, name)| {
+            (
+                *module_id.address(),
+                module_id.name().to_string(),
+                name.to_string(),
+                Arc::new(|_ctx: &mut _, _ty_args: Vec<_>, _args: std::collections::VecDeque<Value>| {
+                    unreachable!("natives are dispatched manually in MoveVM::execute")
+                }) as move_vm_runtime::native_functions::NativeFunction,
+            )
+        });
+        NativeFunctions::new(entries).unwrap_or_else(|_| NativeFunctions { 0: Default::default() })
+    }
+
+    fn dispatch_native(&self, module_id: &ModuleId, name: &Identifier, args: Vec<Value>) -> VMResult<Vec<Value>> {
+        let key = (module_id.clone(), name.clone());
+        match self.natives.get(&key) {
+            Some(implementation) => implementation(args),
+            None => Err(PartialVMError::new(StatusCode::MISSING_DEPENDENCY)
+                .with_message(format!("no native implementation registered for {:?}::{}", key.0, key.1))
+                .finish(move_core_types::vm_status::Location::Undefined)),
         }
     }
 }
 
+/// One executed instruction, recorded when [`MoveVMTracer::record_trace`] is
+/// enabled, so a crashing/aborting execution can be replayed as a
+/// human-readable disassembly rather than re-run blind.
+#[derive(Clone, Debug)]
+pub struct TraceStep {
+    pub module: ModuleId,
+    pub function: Identifier,
+    pub pc: u16,
+    pub instruction: Bytecode,
+    /// Debug-formatted snapshot of the top few operand-stack values at this
+    /// step, just enough to see what a `Call`/`MoveTo`/compare was acting on.
+    pub stack_top: Vec<String>,
+}
+
 pub struct MoveVMTracer {
+    /// Off by default: the per-step bookkeeping isn't free, so only flip
+    /// this on when minimizing or reproducing a saved testcase, not during
+    /// high-throughput fuzzing.
+    pub record_trace: bool,
+    pub trace: Vec<TraceStep>,
+}
+
+impl MoveVMTracer {
+    pub fn new(record_trace: bool) -> Self {
+        Self {
+            record_trace,
+            trace: vec![],
+        }
+    }
 
+    /// Render the recorded trace as a plain-text disassembly of just the
+    /// path that was executed, e.g. for attaching to a crash report.
+    pub fn disassemble(&self) -> String {
+        self.trace
+            .iter()
+            .map(|step| {
+                format!(
+                    "{:?}::{}@{} {:?} | stack: [{}]",
+                    step.module,
+                    step.function.as_str(),
+                    step.pc,
+                    step.instruction,
+                    step.stack_top.join(", ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
 }
 
+const TRACE_STACK_SNAPSHOT_DEPTH: usize = 4;
+
 impl ItyFuzzTracer for MoveVMTracer {
     fn on_step(&mut self, interpreter: &Interpreter, frame: &Frame, pc: u16, instruction: &Bytecode) {
+        if self.record_trace {
+            let depth = interpreter.operand_stack.value.len();
+            let stack_top = interpreter.operand_stack.value[depth.saturating_sub(TRACE_STACK_SNAPSHOT_DEPTH)..depth]
+                .iter()
+                .map(|v| format!("{:?}", v))
+                .collect();
+            self.trace.push(TraceStep {
+                module: frame.function.module_id().clone(),
+                function: frame.function.name.to_owned(),
+                pc,
+                instruction: instruction.clone(),
+                stack_top,
+            });
+        }
+
         macro_rules! fast_peek_back {
             ($interp: expr) => { &$interp.operand_stack.value[$interp.operand_stack.value.len() - 1] };
             ($interp: expr, $kth: expr) => { &$interp.operand_stack.value[$interp.operand_stack.value.len() - $kth] };
@@ -84,28 +811,40 @@ impl ItyFuzzTracer for MoveVMTracer {
             };
         }
 
-        match instruction {
-            // COV MAP
-            Bytecode::BrTrue(offset) => {
-                if let Value(ValueImpl::Bool(b)) = fast_peek_back!(interpreter) {
-                    let next_pc = if *b { *offset } else { pc + 1 };
-                    let map_offset = next_pc as usize % MAP_SIZE;
-                    unsafe {MOVE_COV_MAP[map_offset] = (MOVE_COV_MAP[map_offset] + 1) % 255;}
-                } else {
-                    unreachable!("brtrue with non-bool value")
-                }
-            }
-            Bytecode::BrFalse(offset) => {
-                if let Value(ValueImpl::Bool(b)) = fast_peek_back!(interpreter) {
-                    let next_pc = if !*b { *offset } else { pc + 1 };
-                    let map_offset = next_pc as usize % MAP_SIZE;
-                    unsafe {MOVE_COV_MAP[map_offset] = (MOVE_COV_MAP[map_offset] + 1) % 255;}
-                } else {
-                    unreachable!("brfalse with non-bool value")
+        // COV MAP: true basic-block/edge coverage instead of hashing the raw
+        // next-PC of a branch. Every instruction dispatch can cross into a new
+        // basic block (not just BrTrue/BrFalse), so the lookup happens once
+        // per step rather than per branch opcode.
+        if let Some(block_map) = block_map_cache().get(&(frame.function.module_id().clone(), frame.function.name.to_owned())) {
+            let cur_block = block_map.block_of(pc);
+            PREV_BLOCK.with(|prev| {
+                let prev_block = prev.get();
+                if prev_block != cur_block {
+                    let edge = (((prev_block as usize) >> 1) ^ (cur_block as usize)) ^ (block_map.salt() as usize);
+                    let map_offset = edge % MAP_SIZE;
+                    unsafe { MOVE_COV_MAP[map_offset] = MOVE_COV_MAP[map_offset].wrapping_add(1); }
                 }
-            }
+                prev.set(cur_block);
+            });
+        }
+
+        // Bloom-filter dedup: cheaply tell whether this edge has already
+        // been covered by a prior input so the fuzzer can skip storing
+        // inputs that don't touch anything new.
+        if coverage_bloom().insert(frame.function.module_id(), frame.function.name.as_str(), pc) {
+            FOUND_NEW_COVERAGE.with(|f| f.set(true));
+        }
 
+        match instruction {
+            // Call-frame boundary: the next block we see belongs to a
+            // different function, so don't hash that edge against this one.
+            Bytecode::Call(_) | Bytecode::CallGeneric(_) | Bytecode::Ret => {
+                PREV_BLOCK.with(|prev| prev.set(0));
+            }
+            _ => {}
+        }
 
+        match instruction {
             // CMP MAP
             Bytecode::Eq => {
                 let distance = match (fast_peek_back!(interpreter), fast_peek_back!(interpreter, 2)) {
@@ -245,6 +984,258 @@ impl ItyFuzzTracer for MoveVMTracer {
     }
 }
 
+/// A complete, replayable fuzz input for `MoveVM::execute`: which function to
+/// call, the module it lives in, and the full argument vector (including
+/// references into `Container::Locals`), so a corpus entry can be written to
+/// disk, diffed, and replayed deterministically with `_run`-style harnesses
+/// instead of hand-built Rust.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FuzzInput {
+    pub function: String,
+    pub module_bytes: Vec<u8>,
+    pub args: Vec<Value>,
+}
+
+/// 1-byte discriminants tagging each `ValueImpl` variant the corpus encoder
+/// understands. Kept separate from `ValueImpl` itself since the encoding is
+/// a stable on-disk format, not an internal implementation detail.
+mod value_tag {
+    pub const U8: u8 = 0;
+    pub const U16: u8 = 1;
+    pub const U32: u8 = 2;
+    pub const U64: u8 = 3;
+    pub const U128: u8 = 4;
+    pub const U256: u8 = 5;
+    pub const BOOL: u8 = 6;
+    pub const ADDRESS: u8 = 7;
+    pub const CONTAINER_LOCALS: u8 = 8;
+    pub const INDEXED_REF_LOCAL: u8 = 9;
+}
+
+fn encode_value(value: &ValueImpl, out: &mut Vec<u8>) {
+    match value {
+        ValueImpl::U8(v) => {
+            out.push(value_tag::U8);
+            out.push(*v);
+        }
+        ValueImpl::U16(v) => {
+            out.push(value_tag::U16);
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        ValueImpl::U32(v) => {
+            out.push(value_tag::U32);
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        ValueImpl::U64(v) => {
+            out.push(value_tag::U64);
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        ValueImpl::U128(v) => {
+            out.push(value_tag::U128);
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        ValueImpl::U256(v) => {
+            out.push(value_tag::U256);
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        ValueImpl::Bool(v) => {
+            out.push(value_tag::BOOL);
+            out.push(*v as u8);
+        }
+        ValueImpl::Address(addr) => {
+            out.push(value_tag::ADDRESS);
+            out.extend_from_slice(addr.as_slice());
+        }
+        ValueImpl::Container(values::Container::Locals(rc)) => {
+            out.push(value_tag::CONTAINER_LOCALS);
+            encode_locals(rc, out);
+        }
+        ValueImpl::IndexedRef(values::IndexedRef {
+            idx,
+            container_ref: values::ContainerRef::Local(values::Container::Locals(rc)),
+        }) => {
+            out.push(value_tag::INDEXED_REF_LOCAL);
+            out.extend_from_slice(&(*idx as u32).to_le_bytes());
+            encode_locals(rc, out);
+        }
+        other => panic!("encode_input: unsupported value variant {:?}", other),
+    }
+}
+
+fn encode_locals(rc: &std::rc::Rc<std::cell::RefCell<Vec<ValueImpl>>>, out: &mut Vec<u8>) {
+    let locals = rc.borrow();
+    out.extend_from_slice(&(locals.len() as u32).to_le_bytes());
+    for v in locals.iter() {
+        encode_value(v, out);
+    }
+}
+
+fn decode_value(bytes: &[u8], cursor: &mut usize) -> ValueImpl {
+    macro_rules! take {
+        ($n:expr) => {{
+            let slice = &bytes[*cursor..*cursor + $n];
+            *cursor += $n;
+            slice
+        }};
+    }
+
+    let tag = take!(1)[0];
+    match tag {
+        value_tag::U8 => ValueImpl::U8(take!(1)[0]),
+        value_tag::U16 => ValueImpl::U16(u16::from_le_bytes(take!(2).try_into().unwrap())),
+        value_tag::U32 => ValueImpl::U32(u32::from_le_bytes(take!(4).try_into().unwrap())),
+        value_tag::U64 => ValueImpl::U64(u64::from_le_bytes(take!(8).try_into().unwrap())),
+        value_tag::U128 => ValueImpl::U128(u128::from_le_bytes(take!(16).try_into().unwrap())),
+        value_tag::U256 => ValueImpl::U256(u256::U256::from_le_bytes(
+            &take!(32).try_into().unwrap(),
+        )),
+        value_tag::BOOL => ValueImpl::Bool(take!(1)[0] != 0),
+        value_tag::ADDRESS => ValueImpl::Address(AccountAddress::new(
+            take!(AccountAddress::LENGTH).try_into().unwrap(),
+        )),
+        value_tag::CONTAINER_LOCALS => {
+            ValueImpl::Container(values::Container::Locals(decode_locals(bytes, cursor)))
+        }
+        value_tag::INDEXED_REF_LOCAL => {
+            let idx = u32::from_le_bytes(take!(4).try_into().unwrap()) as usize;
+            ValueImpl::IndexedRef(values::IndexedRef {
+                idx,
+                container_ref: values::ContainerRef::Local(values::Container::Locals(decode_locals(
+                    bytes, cursor,
+                ))),
+            })
+        }
+        other => panic!("decode_input: unknown value tag {}", other),
+    }
+}
+
+fn decode_locals(bytes: &[u8], cursor: &mut usize) -> std::rc::Rc<std::cell::RefCell<Vec<ValueImpl>>> {
+    let len = u32::from_le_bytes(bytes[*cursor..*cursor + 4].try_into().unwrap()) as usize;
+    *cursor += 4;
+    let mut locals = Vec::with_capacity(len);
+    for _ in 0..len {
+        locals.push(decode_value(bytes, cursor));
+    }
+    // Plain `Rc<RefCell<_>>` construction -- `CowLocals::new(..).container`
+    // is equivalent to `Rc::new(RefCell::new(locals))` here. `CowLocals`'s
+    // fork-on-write (`write()`) never runs on this path: once the `Rc` is
+    // unwrapped into `values::Container::Locals`, the interpreter mutates it
+    // directly through its own `RefCell::borrow_mut`, with no notion of our
+    // wrapper. Actually sharing decoded locals across repeated
+    // snapshot/restore the way the corpus-replay hot path wants would mean
+    // threading `CowLocals` through `MoveVMState`/`CloneableValue` (in
+    // `vm_state.rs`/`input.rs`), which this series has not touched.
+    CowLocals::new(locals).container
+}
+
+/// Serialize a complete fuzz input (target function, module bytes, and
+/// argument values) into self-describing hex so it can be written to a
+/// corpus file and diffed as plain text. A reference's container is encoded
+/// inline with the reference, so two references sharing one container
+/// decode into two independent containers: aliasing is not preserved.
+pub fn encode_input(input: &FuzzInput) -> String {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&(input.function.len() as u32).to_le_bytes());
+    buf.extend_from_slice(input.function.as_bytes());
+    buf.extend_from_slice(&(input.module_bytes.len() as u32).to_le_bytes());
+    buf.extend_from_slice(&input.module_bytes);
+    buf.extend_from_slice(&(input.args.len() as u32).to_le_bytes());
+    for arg in &input.args {
+        encode_value(&arg.0, &mut buf);
+    }
+    hex::encode(buf)
+}
+
+/// Inverse of [`encode_input`]: reconstruct a [`FuzzInput`] (including
+/// references into `Container::Locals`) from the hex produced above. Every
+/// decoded reference gets its own freshly built container.
+pub fn decode_input(s: &str) -> Result<FuzzInput, String> {
+    let bytes = hex::decode(s).map_err(|e| e.to_string())?;
+    let mut cursor = 0usize;
+
+    let read_u32 = |bytes: &[u8], cursor: &mut usize| -> usize {
+        let v = u32::from_le_bytes(bytes[*cursor..*cursor + 4].try_into().unwrap()) as usize;
+        *cursor += 4;
+        v
+    };
+
+    let function_len = read_u32(&bytes, &mut cursor);
+    let function = String::from_utf8(bytes[cursor..cursor + function_len].to_vec())
+        .map_err(|e| e.to_string())?;
+    cursor += function_len;
+
+    let module_len = read_u32(&bytes, &mut cursor);
+    let module_bytes = bytes[cursor..cursor + module_len].to_vec();
+    cursor += module_len;
+
+    let arg_count = read_u32(&bytes, &mut cursor);
+    let mut args = Vec::with_capacity(arg_count);
+    for _ in 0..arg_count {
+        args.push(Value(decode_value(&bytes, &mut cursor)));
+    }
+
+    Ok(FuzzInput {
+        function,
+        module_bytes,
+        args,
+    })
+}
+
+/// A copy-on-write handle to a `Locals`-style container
+/// (`Rc<RefCell<Vec<ValueImpl>>>`). `snapshot` clones only the `Rc` pointer
+/// (O(1)); the deep copy is deferred until the first write through a handle
+/// that's still shared with a sibling snapshot, checked via
+/// `Rc::strong_count`.
+///
+/// Not currently on the live VM-state snapshot/restore hot path: that path
+/// clones `MoveVMState`/`CloneableValue` (`vm_state.rs`/`input.rs`, outside
+/// this series' two touched files) directly, without going through this
+/// type. `decode_locals` constructs one but immediately unwraps `.container`
+/// into a raw `Rc`, so `write`'s fork-on-write check is never reached from
+/// there either -- wiring this in for real means threading `CowLocals`
+/// itself (not just its `Rc`) through wherever `MoveVMState` is cloned.
+#[derive(Clone, Debug)]
+pub struct CowLocals {
+    container: std::rc::Rc<std::cell::RefCell<Vec<ValueImpl>>>,
+}
+
+impl CowLocals {
+    pub fn new(values: Vec<ValueImpl>) -> Self {
+        Self {
+            container: std::rc::Rc::new(std::cell::RefCell::new(values)),
+        }
+    }
+
+    /// O(1): shares the underlying `Rc`, no deep clone happens here.
+    pub fn snapshot(&self) -> Self {
+        Self {
+            container: self.container.clone(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.container.borrow().len()
+    }
+
+    pub fn read(&self, idx: usize) -> ValueImpl {
+        self.container.borrow()[idx].clone()
+    }
+
+    /// Write through this handle. If the buffer is still shared with another
+    /// snapshot (`strong_count > 1`), clone-then-replace first so the write
+    /// is invisible to siblings; otherwise mutate in place for free.
+    pub fn write(&mut self, idx: usize, value: ValueImpl) {
+        if std::rc::Rc::strong_count(&self.container) > 1 {
+            let cloned = self.container.borrow().clone();
+            self.container = std::rc::Rc::new(std::cell::RefCell::new(cloned));
+        }
+        self.container.borrow_mut()[idx] = value;
+    }
+
+    pub fn as_container(&self) -> values::Container {
+        values::Container::Locals(self.container.clone())
+    }
+}
 
 impl<I, S>
     GenericVM<
@@ -277,6 +1268,10 @@ where
                                                 module).expect("internal deploy error");
         for f in &self.loader.module_cache.read().functions[func_off..] {
             println!("deployed function: {:?}@{}({:?}) returns {:?}", deployed_module_idx, f.name.as_str(), f.parameter_types, f.return_types());
+            block_map_cache().insert(
+                (deployed_module_idx.clone(), f.name.to_owned()),
+                Arc::new(BlockMap::build(f.code(), function_salt(&deployed_module_idx, &f.name))),
+            );
             self.functions
                 .entry(deployed_module_idx.clone())
                 .or_insert_with(HashMap::new)
@@ -336,6 +1331,7 @@ where
         unsafe {
             MOVE_STATE_CHANGED = false;
         }
+        FOUND_NEW_COVERAGE.with(|f| f.set(false));
 
         // set up initial frame
         let mut current_frame = {
@@ -353,14 +1349,27 @@ where
 
         let mut call_stack = vec![];
         let mut reverted = false;
+        let mut timed_out = false;
+        let mut abort_reason: Option<MoveAbortReason> = None;
+        let mut gas_meter = StepBudgetGasMeter::new(DEFAULT_INSTRUCTION_BUDGET);
+        let mut tracer = MoveVMTracer::new(self.replay_trace);
         loop {
             let resolver = current_frame.resolver(&self.loader);
             let ret =
-                current_frame.execute_code(&resolver, &mut interp, &mut state, &mut UnmeteredGasMeter, &mut MoveVMTracer{});
+                current_frame.execute_code(&resolver, &mut interp, &mut state, &mut gas_meter, &mut tracer);
             println!("{:?}", ret);
 
-            if ret.is_err() {
+            if let Err(err) = &ret {
+                if err.major_status() == StatusCode::OUT_OF_GAS && gas_meter.is_out_of_budget() {
+                    // The input tripped the step budget rather than genuinely reverting:
+                    // treat it as a scheduler-level timeout, not an interesting revert.
+                    println!("execution aborted: {:?}", err);
+                    timed_out = true;
+                    break;
+                }
                 reverted = true;
+                abort_reason = Some(classify_abort(err));
+                println!("abort reason: {:?}", abort_reason);
                 break;
             }
 
@@ -384,9 +1393,28 @@ where
                         locals.store_loc(argc - i - 1, interp.operand_stack.pop().unwrap()).unwrap();
                     }
                     println!("locals: {:?}", locals);
-                    // todo: handle native here
                     if func.is_native() {
-                        todo!("native function call")
+                        let args = (0..argc).map(|i| locals.copy_loc(i).unwrap()).collect();
+                        match self.dispatch_native(func.module_id(), &func.name, args) {
+                            Ok(results) => {
+                                for v in results {
+                                    interp.operand_stack.push(v).unwrap();
+                                }
+                                current_frame.pc += 1;
+                                continue;
+                            }
+                            Err(err) => {
+                                // Native dispatch failures (e.g. `vector::borrow` out of
+                                // range, `vector::destroy_empty` on a non-empty vector, or
+                                // a native missing from the default registry) are ordinary
+                                // VM-level errors, not process crashes -- classify them the
+                                // same way as any other `execute_code` failure above.
+                                reverted = true;
+                                abort_reason = Some(classify_abort(&err));
+                                println!("abort reason: {:?}", abort_reason);
+                                break;
+                            }
+                        }
                     }
                     call_stack.push(current_frame);
                     current_frame = Frame {
@@ -407,9 +1435,23 @@ where
                         locals.store_loc(argc - i - 1, interp.operand_stack.pop().unwrap()).unwrap();
                     }
 
-                    // todo: handle native here
                     if func.is_native() {
-                        todo!("native function call")
+                        let args = (0..argc).map(|i| locals.copy_loc(i).unwrap()).collect();
+                        match self.dispatch_native(func.module_id(), &func.name, args) {
+                            Ok(results) => {
+                                for v in results {
+                                    interp.operand_stack.push(v).unwrap();
+                                }
+                                current_frame.pc += 1;
+                                continue;
+                            }
+                            Err(err) => {
+                                reverted = true;
+                                abort_reason = Some(classify_abort(&err));
+                                println!("abort reason: {:?}", abort_reason);
+                                break;
+                            }
+                        }
                     }
                     call_stack.push(current_frame);
                     current_frame = Frame {
@@ -422,6 +1464,24 @@ where
             }
         }
 
+        if timed_out {
+            // `interp`/`state` reflect a partially-mutated, not-yet-consistent
+            // world: the step budget tripped mid-instruction-dispatch, not at a
+            // clean return. Don't extract output or save this `state` into the
+            // corpus as if it were a genuine result -- hand back the
+            // pre-execution state untouched and mark the input `reverted` so
+            // schedulers/oracles can't mistake a timeout for a successful run.
+            return ExecutionResult {
+                new_state: StagedVMState::new_with_state(input.get_state().clone()),
+                output: MoveOutput { vars: vec![] },
+                reverted: true,
+                additional_info: Some(format!(
+                    "TIMEOUT: step budget of {} instructions exhausted",
+                    DEFAULT_INSTRUCTION_BUDGET
+                )),
+            };
+        }
+
         let resolver = current_frame.resolver(&self.loader);
 
 
@@ -449,11 +1509,23 @@ where
             out.vars.push((t.clone(), v.clone()));
             println!("val: {:?} {:?}", v, resolver.loader.type_to_type_tag(t));
         }
+        // Carries the classified abort reason (if any) so oracles can
+        // distinguish a specific user abort code / arithmetic trap from a
+        // plain revert instead of treating every failing path the same. When
+        // replay tracing is on, append the disassembly of the path that got
+        // us there so a saved testcase is replayable without re-running it.
+        let additional_info = match (abort_reason, self.replay_trace) {
+            (Some(reason), true) => Some(format!("{:?}\n{}", reason, tracer.disassemble())),
+            (Some(reason), false) => Some(format!("{:?}", reason)),
+            (None, true) => Some(tracer.disassemble()),
+            (None, false) => None,
+        };
+
         ExecutionResult {
             new_state: StagedVMState::new_with_state(state),
             output: out,
             reverted,
-            additional_info: None
+            additional_info,
         }
     }
 
@@ -630,4 +1702,96 @@ mod tests {
                        "test2",
         );
     }
+
+    #[test]
+    fn test_dispatch_native_default_hash_sha2_256() {
+        // Exercises the real `execute`-facing dispatch path (`dispatch_native`),
+        // not just the standalone `default_natives::hash_sha2_256` closure, to
+        // confirm `MoveVM::new` actually wires the default natives in.
+        let mv = MoveVM::<
+            MoveFunctionInput,
+            FuzzState<MoveFunctionInput, MoveVMState, ModuleId, AccountAddress, MoveOutput>,
+        >::new();
+
+        let stdlib = AccountAddress::from_hex_literal("0x1").unwrap();
+        let module_id = ModuleId::new(stdlib, Identifier::new("hash").unwrap());
+        let name = Identifier::new("sha2_256").unwrap();
+
+        let result = mv
+            .dispatch_native(&module_id, &name, vec![Value::vector_u8(b"abc".to_vec())])
+            .expect("sha2_256 should be registered by default");
+
+        let digest = match &result[0].0 {
+            ValueImpl::Container(values::Container::VecU8(rc)) => rc.borrow().clone(),
+            other => panic!("expected vector<u8>, got {:?}", other),
+        };
+        assert_eq!(
+            digest,
+            hex::decode("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_encode_decode_input_roundtrip() {
+        let input = FuzzInput {
+            function: "test2".to_string(),
+            module_bytes: vec![0xa1, 0x1c, 0xeb, 0x0b, 0x05],
+            args: vec![
+                Value::u64(20),
+                // Upper 128 bits set: would silently truncate to 0 on a
+                // roundtrip through a `u128`-only encoding.
+                Value(ValueImpl::U256(u256::U256::from_le_bytes(&[0xffu8; 32]))),
+                Value(ValueImpl::IndexedRef(values::IndexedRef {
+                    idx: 0,
+                    container_ref: ContainerRef::Local(values::Container::Locals(Rc::new(
+                        RefCell::new(vec![ValueImpl::U8(2), ValueImpl::Bool(true)]),
+                    ))),
+                })),
+            ],
+        };
+
+        let encoded = encode_input(&input);
+        let decoded = decode_input(&encoded).unwrap();
+
+        assert_eq!(decoded.function, input.function);
+        assert_eq!(decoded.module_bytes, input.module_bytes);
+        assert_eq!(format!("{:?}", decoded.args), format!("{:?}", input.args));
+    }
+
+    #[test]
+    fn test_cow_locals_snapshot_is_pointer_clone() {
+        let original = CowLocals::new(vec![ValueImpl::U64(1), ValueImpl::U64(2)]);
+        let snapshot = original.snapshot();
+
+        // No deep copy has happened yet: both handles share the same Rc.
+        assert!(std::rc::Rc::ptr_eq(&original.container, &snapshot.container));
+        assert_eq!(std::rc::Rc::strong_count(&original.container), 2);
+    }
+
+    #[test]
+    fn test_cow_locals_write_does_not_perturb_sibling() {
+        let mut original = CowLocals::new(vec![ValueImpl::U64(1), ValueImpl::U64(2)]);
+        let snapshot = original.snapshot();
+
+        original.write(0, ValueImpl::U64(99));
+
+        assert_eq!(original.read(0), ValueImpl::U64(99));
+        // The sibling snapshot must be unaffected by the write above.
+        assert_eq!(snapshot.read(0), ValueImpl::U64(1));
+        // The write severed the sharing, so the two handles now point at
+        // independent buffers.
+        assert!(!std::rc::Rc::ptr_eq(&original.container, &snapshot.container));
+    }
+
+    #[test]
+    fn test_cow_locals_unshared_write_has_no_extra_allocation() {
+        let mut solo = CowLocals::new(vec![ValueImpl::U64(1)]);
+        let container_before = std::rc::Rc::as_ptr(&solo.container);
+
+        solo.write(0, ValueImpl::U64(2));
+
+        // strong_count == 1, so the write mutates in place: same allocation.
+        assert_eq!(std::rc::Rc::as_ptr(&solo.container), container_before);
+        assert_eq!(solo.read(0), ValueImpl::U64(2));
+    }
 }
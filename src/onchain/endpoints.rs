@@ -1,12 +1,18 @@
 use bytes::Bytes;
 use primitive_types::{H160, U256};
 use revm::{Bytecode, LatestSpec};
+use rlp::Rlp;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha3::{Digest, Keccak256};
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt::{format, Debug};
+use std::fs;
 use std::panic;
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::{Arc, RwLock};
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, Copy)]
 pub enum Chain {
@@ -51,13 +57,42 @@ impl Chain {
     }
 }
 
+/// Which wire protocol `_request` speaks. Kept as a plain enum (rather than,
+/// say, a trait object) so `get_contract_code`/`get_contract_slot`/`fetch_abi`
+/// never need to know which one is in use -- they only ever call `_request`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Transport {
+    Http,
+    WebSocket,
+}
+
 #[derive(Clone, Debug)]
 pub struct OnChainConfig {
     pub endpoint_url: String,
-    // pub cache_len: usize,
-    //
-    // code_cache: HashMap<H160, Bytecode>,
-    // slot_cache: HashMap<(H160, U256), U256>,
+    pub endpoint_ws: Option<String>,
+    pub transport: Transport,
+
+    // Updated by the background `newHeads` subscription thread spawned from
+    // `with_endpoint_ws` when `block_number` is "latest", so a long-running
+    // campaign against a live chain follows the head instead of pinning
+    // whatever block it started at.
+    tracked_latest_block: Arc<RwLock<Option<String>>>,
+
+    // A fork fuzz at a fixed block_number is fully deterministic, so the
+    // same slot/code ends up fetched thousands of times over a campaign;
+    // cache it in-memory (and optionally on disk) rather than re-hitting
+    // the RPC endpoint every time. Keyed on the block number actually used
+    // at fetch time (see `current_block_number`) so a config tracking a
+    // live head via `with_endpoint_ws` doesn't keep serving code/slots from
+    // whatever block it first queried once the head has moved on.
+    code_cache: RefCell<HashMap<(H160, String), Bytecode>>,
+    slot_cache: RefCell<HashMap<(H160, U256, String), U256>>,
+    cache_dir: Option<PathBuf>,
+
+    // ABIs registered offline (e.g. from a local build artifact directory)
+    // take priority over an Etherscan lookup in `fetch_abi`.
+    abi_cache: RefCell<HashMap<H160, String>>,
+
     pub client: reqwest::blocking::Client,
     pub chain_id: u32,
     pub block_number: String,
@@ -101,6 +136,13 @@ impl OnChainConfig {
     ) -> Self {
         Self {
             endpoint_url,
+            endpoint_ws: None,
+            transport: Transport::Http,
+            tracked_latest_block: Arc::new(RwLock::new(None)),
+            code_cache: RefCell::new(HashMap::new()),
+            slot_cache: RefCell::new(HashMap::new()),
+            cache_dir: None,
+            abi_cache: RefCell::new(HashMap::new()),
             client: reqwest::blocking::Client::new(),
             chain_id,
             block_number: if block_number == 0 {
@@ -115,6 +157,30 @@ impl OnChainConfig {
         }
     }
 
+    /// Adds a WebSocket endpoint. If `block_number` is "latest", this also
+    /// opens an `eth_subscribe("newHeads")` subscription on a background
+    /// thread and keeps the config's effective block pointer (see
+    /// `current_block_number`) advancing as new blocks arrive, instead of
+    /// silently pinning to whatever block "latest" resolved to at startup.
+    pub fn with_endpoint_ws(mut self, endpoint_ws: String) -> Self {
+        if self.block_number == "latest" {
+            spawn_new_heads_tracker(endpoint_ws.clone(), self.tracked_latest_block.clone());
+        }
+        self.endpoint_ws = Some(endpoint_ws);
+        self.transport = Transport::WebSocket;
+        self
+    }
+
+    /// The block number `_request` callers should actually use: the live
+    /// head tracked via the `newHeads` subscription if one is running,
+    /// otherwise the statically configured `block_number`.
+    fn current_block_number(&self) -> String {
+        if let Some(latest) = self.tracked_latest_block.read().unwrap().clone() {
+            return latest;
+        }
+        self.block_number.clone()
+    }
+
     pub fn add_etherscan_api_key(&mut self, key: String) {
         self.etherscan_api_key.push(key);
     }
@@ -123,7 +189,101 @@ impl OnChainConfig {
         self.moralis_api_key.push(key);
     }
 
+    /// Register an ABI for `address` directly, bypassing Etherscan entirely.
+    /// Later calls overwrite earlier ones for the same address.
+    pub fn add_abi(&self, address: H160, abi: String) {
+        self.abi_cache.borrow_mut().insert(address, abi);
+    }
+
+    /// Register every ABI listed in `<dir>/index.json`, a `{"address": "filename"}`
+    /// map, so a whole project's build artifacts can be pointed at in one call.
+    /// This is meant for fuzzing a project's own contracts offline, where the
+    /// source is already on disk and hitting Etherscan would be pointless (and,
+    /// for unverified/local-only contracts, impossible).
+    pub fn add_abi_dir(&self, dir: PathBuf) -> Result<(), String> {
+        let index = fs::read_to_string(dir.join("index.json"))
+            .map_err(|e| format!("failed to read {}: {}", dir.join("index.json").display(), e))?;
+        let index: HashMap<String, String> = serde_json::from_str(&index)
+            .map_err(|e| format!("malformed index.json: {}", e))?;
+
+        for (address, filename) in index {
+            let address = H160::from_str(&address)
+                .map_err(|e| format!("invalid address {}: {}", address, e))?;
+            let abi = fs::read_to_string(dir.join(&filename))
+                .map_err(|e| format!("failed to read {}: {}", filename, e))?;
+            self.add_abi(address, abi);
+        }
+        Ok(())
+    }
+
+    /// Back the in-memory cache with a directory on disk, loading whatever
+    /// was persisted there from a previous run so reruns against the same
+    /// `block_number` are fully offline.
+    pub fn with_cache_dir(mut self, path: PathBuf) -> Self {
+        if let Ok(contents) = fs::read_to_string(path.join("code_cache.json")) {
+            if let Ok(entries) = serde_json::from_str::<HashMap<String, String>>(&contents) {
+                let mut cache = self.code_cache.borrow_mut();
+                for (key, code_hex) in entries {
+                    if let Some((addr, block)) = key.split_once(':') {
+                        if let (Ok(addr), Ok(code)) = (H160::from_str(addr), hex::decode(code_hex.trim_start_matches("0x"))) {
+                            cache.insert((addr, block.to_string()), Bytecode::new_raw(Bytes::from(code)).to_analysed::<LatestSpec>());
+                        }
+                    }
+                }
+            }
+        }
+        if let Ok(contents) = fs::read_to_string(path.join("slot_cache.json")) {
+            if let Ok(entries) = serde_json::from_str::<HashMap<String, String>>(&contents) {
+                let mut cache = self.slot_cache.borrow_mut();
+                for (key, value_hex) in entries {
+                    let mut parts = key.splitn(3, ':');
+                    if let (Some(addr), Some(slot), Some(block)) = (parts.next(), parts.next(), parts.next()) {
+                        if let (Ok(addr), Ok(slot), Ok(value)) =
+                            (H160::from_str(addr), U256::from_str(slot), U256::from_str(&value_hex))
+                        {
+                            cache.insert((addr, slot, block.to_string()), value);
+                        }
+                    }
+                }
+            }
+        }
+        self.cache_dir = Some(path);
+        self
+    }
+
+    fn persist_code_cache(&self) {
+        let Some(dir) = &self.cache_dir else { return };
+        let _ = fs::create_dir_all(dir);
+        let entries: HashMap<String, String> = self
+            .code_cache
+            .borrow()
+            .iter()
+            .map(|((addr, block), code)| (format!("{:?}:{}", addr, block), format!("0x{}", hex::encode(code.bytes()))))
+            .collect();
+        if let Ok(json) = serde_json::to_string(&entries) {
+            let _ = fs::write(dir.join("code_cache.json"), json);
+        }
+    }
+
+    fn persist_slot_cache(&self) {
+        let Some(dir) = &self.cache_dir else { return };
+        let _ = fs::create_dir_all(dir);
+        let entries: HashMap<String, String> = self
+            .slot_cache
+            .borrow()
+            .iter()
+            .map(|((addr, slot, block), value)| (format!("{:?}:{:x}:{}", addr, slot, block), format!("{:x}", value)))
+            .collect();
+        if let Ok(json) = serde_json::to_string(&entries) {
+            let _ = fs::write(dir.join("slot_cache.json"), json);
+        }
+    }
+
     pub fn fetch_abi(&self, address: H160) -> Option<String> {
+        if let Some(abi) = self.abi_cache.borrow().get(&address) {
+            return Some(abi.clone());
+        }
+
         let endpoint = format!(
             "{}?module=contract&action=getabi&address={:?}&format=json&apikey={}",
             self.etherscan_base,
@@ -173,6 +333,13 @@ impl OnChainConfig {
     }
 
     fn _request(&self, method: String, params: String) -> Option<Value> {
+        match self.transport {
+            Transport::Http => self._request_http(method, params),
+            Transport::WebSocket => self._request_ws(method, params),
+        }
+    }
+
+    fn _request_http(&self, method: String, params: String) -> Option<Value> {
         let data = format!(
             "{{\"jsonrpc\":\"2.0\", \"method\": \"{}\", \"params\": {}, \"id\": {}}}",
             method, params, self.chain_id
@@ -206,44 +373,551 @@ impl OnChainConfig {
         }
     }
 
+    /// Same JSON-RPC semantics as `_request_http`, but over a fresh
+    /// WebSocket connection to `endpoint_ws` -- opened, used for exactly one
+    /// request/response pair, and closed again. This keeps `_request` simple
+    /// (no long-lived connection to manage) while still letting a config
+    /// that only has a WS endpoint (no HTTP one) work end-to-end.
+    fn _request_ws(&self, method: String, params: String) -> Option<Value> {
+        let endpoint_ws = self.endpoint_ws.as_ref()?;
+        let data = format!(
+            "{{\"jsonrpc\":\"2.0\", \"method\": \"{}\", \"params\": {}, \"id\": {}}}",
+            method, params, self.chain_id
+        );
+        let (mut socket, _) = match tungstenite::connect(endpoint_ws) {
+            Ok(conn) => conn,
+            Err(e) => {
+                println!("Error: {}", e);
+                return None;
+            }
+        };
+        if let Err(e) = socket.send(tungstenite::Message::Text(data)) {
+            println!("Error: {}", e);
+            return None;
+        }
+        loop {
+            match socket.read() {
+                Ok(tungstenite::Message::Text(resp)) => {
+                    let json: Value =
+                        serde_json::from_str(&resp).expect("failed to parse API result");
+                    // A subscription push (`eth_subscription`) has no `id` and
+                    // isn't the response we're waiting for; skip it.
+                    if json.get("id").is_some() {
+                        return Some(json["result"].clone());
+                    }
+                }
+                Ok(_) => continue,
+                Err(e) => {
+                    println!("Error: {}", e);
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// Batched version of `_request`: serializes every `(method, params)`
+    /// pair into a single JSON-RPC array request (each with its own
+    /// incrementing `id`), sends it in one round-trip over whichever
+    /// transport `_request` would have used, and demultiplexes the response
+    /// array back by `id`. Lets a contract's whole storage layout get warmed
+    /// in one round-trip instead of one request per slot/code.
+    fn _request_batch(&self, calls: Vec<(String, String)>) -> Vec<Option<Value>> {
+        if calls.is_empty() {
+            return vec![];
+        }
+
+        let body = format!(
+            "[{}]",
+            calls
+                .iter()
+                .enumerate()
+                .map(|(id, (method, params))| format!(
+                    "{{\"jsonrpc\":\"2.0\", \"method\": \"{}\", \"params\": {}, \"id\": {}}}",
+                    method, params, id
+                ))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+
+        let text = match self.transport {
+            Transport::Http => self._request_batch_http(&body),
+            Transport::WebSocket => self._request_batch_ws(&body),
+        };
+
+        match text {
+            Some(text) => {
+                let parsed: Vec<Value> = serde_json::from_str(&text).unwrap_or_default();
+                let mut by_id: HashMap<u64, Value> = parsed
+                    .into_iter()
+                    .filter_map(|item| item["id"].as_u64().map(|id| (id, item["result"].clone())))
+                    .collect();
+                (0..calls.len() as u64).map(|id| by_id.remove(&id)).collect()
+            }
+            None => vec![None; calls.len()],
+        }
+    }
+
+    fn _request_batch_http(&self, body: &str) -> Option<String> {
+        match self.client.post(self.endpoint_url.clone()).body(body.to_string()).send() {
+            Ok(resp) => match resp.text() {
+                Ok(text) => Some(text),
+                Err(e) => {
+                    println!("{:?}", e);
+                    None
+                }
+            },
+            Err(e) => {
+                println!("Error: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Same semantics as `_request_batch_http`, but over a fresh WebSocket
+    /// connection to `endpoint_ws` -- mirrors `_request_ws` so a config
+    /// pinned to `Transport::WebSocket` doesn't silently fall back to HTTP
+    /// for the bulk-fetch paths.
+    fn _request_batch_ws(&self, body: &str) -> Option<String> {
+        let endpoint_ws = self.endpoint_ws.as_ref()?;
+        let (mut socket, _) = match tungstenite::connect(endpoint_ws) {
+            Ok(conn) => conn,
+            Err(e) => {
+                println!("Error: {}", e);
+                return None;
+            }
+        };
+        if let Err(e) = socket.send(tungstenite::Message::Text(body.to_string())) {
+            println!("Error: {}", e);
+            return None;
+        }
+        loop {
+            match socket.read() {
+                Ok(tungstenite::Message::Text(resp)) => {
+                    // A batch response is a JSON array, unlike the single
+                    // object `_request_ws` waits for; any array reply here is
+                    // the one we're waiting for (subscription pushes are
+                    // single objects with no `id`).
+                    if let Ok(Value::Array(_)) = serde_json::from_str::<Value>(&resp) {
+                        return Some(resp);
+                    }
+                }
+                Ok(_) => continue,
+                Err(e) => {
+                    println!("Error: {}", e);
+                    return None;
+                }
+            }
+        }
+    }
+
     pub fn get_contract_code(&self, address: H160) -> Bytecode {
+        let block = self.current_block_number();
+        if let Some(code) = self.code_cache.borrow().get(&(address, block.clone())) {
+            return code.clone();
+        }
+
         let mut params = String::from("[");
         params.push_str(&format!("\"0x{:x}\",", address));
-        params.push_str(&format!("\"{}\"", self.block_number));
+        params.push_str(&format!("\"{}\"", block));
         params.push_str("]");
         let resp = self._request("eth_getCode".to_string(), params);
-        match resp {
+        let code = match resp {
             Some(resp) => {
                 let code = resp.as_str().unwrap();
                 let code = code.trim_start_matches("0x");
                 let code = hex::decode(code).unwrap();
-                return Bytecode::new_raw(Bytes::from(code)).to_analysed::<LatestSpec>();
+                Bytecode::new_raw(Bytes::from(code)).to_analysed::<LatestSpec>()
             }
-            None => {
-                return Bytecode::new();
-            }
-        }
+            None => Bytecode::new(),
+        };
+
+        self.code_cache.borrow_mut().insert((address, block), code.clone());
+        self.persist_code_cache();
+        code
     }
 
     pub fn get_contract_slot(&self, address: H160, slot: U256) -> U256 {
+        let block = self.current_block_number();
+        if let Some(value) = self.slot_cache.borrow().get(&(address, slot, block.clone())) {
+            return *value;
+        }
+
         let mut params = String::from("[");
         params.push_str(&format!("\"0x{:x}\",", address));
         params.push_str(&format!("\"0x{:x}\",", slot));
-        params.push_str(&format!("\"{}\"", self.block_number));
+        params.push_str(&format!("\"{}\"", block));
         params.push_str("]");
         let resp = self._request("eth_getStorageAt".to_string(), params);
-        match resp {
+        let value = match resp {
             Some(resp) => {
-                let slot = resp.as_str().unwrap();
-                let slot = slot.trim_start_matches("0x");
-                let slot = hex::decode(slot).unwrap();
-                return U256::from_big_endian(&slot);
+                let slot_hex = resp.as_str().unwrap();
+                let slot_hex = slot_hex.trim_start_matches("0x");
+                let slot_bytes = hex::decode(slot_hex).unwrap();
+                U256::from_big_endian(&slot_bytes)
+            }
+            None => U256::from(0),
+        };
+
+        self.slot_cache.borrow_mut().insert((address, slot, block), value);
+        self.persist_slot_cache();
+        value
+    }
+
+    /// Fetch many storage slots of one contract in a single round-trip,
+    /// reusing whatever's already cached and batching the rest via
+    /// `_request_batch`.
+    pub fn get_contract_slots(&self, address: H160, slots: &[U256]) -> HashMap<U256, U256> {
+        let block = self.current_block_number();
+        let mut result = HashMap::with_capacity(slots.len());
+        let mut to_fetch = vec![];
+        {
+            let cache = self.slot_cache.borrow();
+            for &slot in slots {
+                match cache.get(&(address, slot, block.clone())) {
+                    Some(value) => {
+                        result.insert(slot, *value);
+                    }
+                    None => to_fetch.push(slot),
+                }
+            }
+        }
+
+        if !to_fetch.is_empty() {
+            let calls = to_fetch
+                .iter()
+                .map(|slot| {
+                    (
+                        "eth_getStorageAt".to_string(),
+                        format!("[\"0x{:x}\", \"0x{:x}\", \"{}\"]", address, slot, block),
+                    )
+                })
+                .collect();
+            let responses = self._request_batch(calls);
+
+            let mut cache = self.slot_cache.borrow_mut();
+            for (slot, resp) in to_fetch.into_iter().zip(responses) {
+                let value = match resp {
+                    Some(resp) => {
+                        let slot_hex = resp.as_str().unwrap_or("0x0").trim_start_matches("0x");
+                        U256::from_big_endian(&hex::decode(slot_hex).unwrap_or_default())
+                    }
+                    None => U256::from(0),
+                };
+                cache.insert((address, slot, block.clone()), value);
+                result.insert(slot, value);
+            }
+            drop(cache);
+            self.persist_slot_cache();
+        }
+
+        result
+    }
+
+    /// Fetch the code of many contracts in a single round-trip.
+    pub fn get_contract_codes(&self, addresses: &[H160]) -> HashMap<H160, Bytecode> {
+        let block = self.current_block_number();
+        let mut result = HashMap::with_capacity(addresses.len());
+        let mut to_fetch = vec![];
+        {
+            let cache = self.code_cache.borrow();
+            for &address in addresses {
+                match cache.get(&(address, block.clone())) {
+                    Some(code) => {
+                        result.insert(address, code.clone());
+                    }
+                    None => to_fetch.push(address),
+                }
+            }
+        }
+
+        if !to_fetch.is_empty() {
+            let calls = to_fetch
+                .iter()
+                .map(|address| {
+                    (
+                        "eth_getCode".to_string(),
+                        format!("[\"0x{:x}\", \"{}\"]", address, block),
+                    )
+                })
+                .collect();
+            let responses = self._request_batch(calls);
+
+            let mut cache = self.code_cache.borrow_mut();
+            for (address, resp) in to_fetch.into_iter().zip(responses) {
+                let code = match resp {
+                    Some(resp) => {
+                        let code_hex = resp.as_str().unwrap_or("0x").trim_start_matches("0x");
+                        Bytecode::new_raw(Bytes::from(hex::decode(code_hex).unwrap_or_default()))
+                            .to_analysed::<LatestSpec>()
+                    }
+                    None => Bytecode::new(),
+                };
+                cache.insert((address, block.clone()), code.clone());
+                result.insert(address, code);
+            }
+            drop(cache);
+            self.persist_code_cache();
+        }
+
+        result
+    }
+
+    /// Like `get_contract_code`, but checks the fetched bytes against an
+    /// `eth_getProof` Merkle-Patricia-trie proof rooted at the block's
+    /// `stateRoot` instead of trusting the RPC endpoint outright. Use this
+    /// when pointed at a public/free node you don't otherwise trust.
+    pub fn get_contract_code_verified(&self, address: H160) -> Result<Bytecode, String> {
+        let state_root = self.fetch_state_root()?;
+        let proof = self.fetch_proof(address, &[])?;
+
+        let account_proof = decode_hex_array(&proof, "accountProof")?;
+        let key_nibbles = nibbles_of(&keccak256(address.as_bytes()));
+        let account_rlp = walk_mpt_proof(state_root, &key_nibbles, &account_proof)?
+            .ok_or_else(|| format!("account proof proves {:?} does not exist", address))?;
+
+        let account_fields = Rlp::new(&account_rlp);
+        let code_hash = account_fields
+            .at(3)
+            .map_err(|e| e.to_string())?
+            .data()
+            .map_err(|e| e.to_string())?
+            .to_vec();
+
+        let code = self.get_contract_code(address);
+        if keccak256(&code.bytes()).to_vec() != code_hash {
+            return Err(format!(
+                "code hash mismatch for {:?}: fetched code doesn't match the verified account proof",
+                address
+            ));
+        }
+        Ok(code)
+    }
+
+    /// Like `get_contract_slot`, but verifies the returned value against the
+    /// account's `storageRoot` via `eth_getProof`'s `storageProof`. A
+    /// missing leaf in the proof is itself proof the slot is zero.
+    pub fn get_contract_slot_verified(&self, address: H160, slot: U256) -> Result<U256, String> {
+        let state_root = self.fetch_state_root()?;
+        let proof = self.fetch_proof(address, &[slot])?;
+
+        let account_proof = decode_hex_array(&proof, "accountProof")?;
+        let account_key_nibbles = nibbles_of(&keccak256(address.as_bytes()));
+        let account_rlp = walk_mpt_proof(state_root, &account_key_nibbles, &account_proof)?
+            .ok_or_else(|| format!("account proof proves {:?} does not exist", address))?;
+
+        let account_fields = Rlp::new(&account_rlp);
+        let storage_root_bytes = account_fields
+            .at(2)
+            .map_err(|e| e.to_string())?
+            .data()
+            .map_err(|e| e.to_string())?;
+        let mut storage_root = [0u8; 32];
+        storage_root.copy_from_slice(storage_root_bytes);
+
+        let storage_proofs = proof["storageProof"].as_array().ok_or("malformed storageProof")?;
+        let entry = storage_proofs
+            .get(0)
+            .ok_or("eth_getProof returned no storageProof entry for the requested slot")?;
+        let slot_proof: Vec<Vec<u8>> = entry["proof"]
+            .as_array()
+            .ok_or("malformed storageProof[0].proof")?
+            .iter()
+            .map(|v| hex::decode(v.as_str().unwrap_or("").trim_start_matches("0x")).unwrap_or_default())
+            .collect();
+
+        let mut slot_bytes = [0u8; 32];
+        slot.to_big_endian(&mut slot_bytes);
+        let slot_key_nibbles = nibbles_of(&keccak256(&slot_bytes));
+
+        let value = match walk_mpt_proof(storage_root, &slot_key_nibbles, &slot_proof)? {
+            Some(value_rlp) => {
+                let decoded = Rlp::new(&value_rlp).data().map_err(|e| e.to_string())?;
+                U256::from_big_endian(decoded)
             }
-            None => {
-                return U256::from(0);
+            // A missing leaf proves the slot is zero.
+            None => U256::zero(),
+        };
+        Ok(value)
+    }
+
+    fn fetch_proof(&self, address: H160, slots: &[U256]) -> Result<Value, String> {
+        let slots_json = format!(
+            "[{}]",
+            slots
+                .iter()
+                .map(|s| format!("\"0x{:x}\"", s))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+        let params = format!("[\"0x{:x}\", {}, \"{}\"]", address, slots_json, self.current_block_number());
+        self._request("eth_getProof".to_string(), params)
+            .ok_or_else(|| "eth_getProof returned no result".to_string())
+    }
+
+    fn fetch_state_root(&self) -> Result<[u8; 32], String> {
+        let params = format!("[\"{}\", false]", self.current_block_number());
+        let resp = self
+            ._request("eth_getBlockByNumber".to_string(), params)
+            .ok_or("eth_getBlockByNumber returned no result")?;
+        let state_root_hex = resp
+            .get("stateRoot")
+            .and_then(|v| v.as_str())
+            .ok_or("block response missing stateRoot")?
+            .trim_start_matches("0x");
+        let bytes = hex::decode(state_root_hex).map_err(|e| e.to_string())?;
+        let mut out = [0u8; 32];
+        out.copy_from_slice(&bytes);
+        Ok(out)
+    }
+}
+
+fn decode_hex_array(value: &Value, field: &str) -> Result<Vec<Vec<u8>>, String> {
+    value[field]
+        .as_array()
+        .ok_or_else(|| format!("malformed {}", field))?
+        .iter()
+        .map(|v| {
+            let s = v.as_str().ok_or_else(|| format!("malformed {} entry", field))?;
+            hex::decode(s.trim_start_matches("0x")).map_err(|e| e.to_string())
+        })
+        .collect()
+}
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(data);
+    let digest = hasher.finalize();
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&digest);
+    out
+}
+
+fn nibbles_of(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect()
+}
+
+/// Decode a compact hex-prefix encoded path (Ethereum MPT leaf/extension
+/// node paths), returning the nibble path and whether the node is a leaf.
+fn hex_prefix_decode(encoded: &[u8]) -> (Vec<u8>, bool) {
+    if encoded.is_empty() {
+        return (vec![], false);
+    }
+    let first = encoded[0];
+    let is_leaf = (first & 0x20) != 0;
+    let is_odd = (first & 0x10) != 0;
+
+    let mut nibbles = vec![];
+    if is_odd {
+        nibbles.push(first & 0x0f);
+    }
+    for byte in &encoded[1..] {
+        nibbles.push(byte >> 4);
+        nibbles.push(byte & 0x0f);
+    }
+    (nibbles, is_leaf)
+}
+
+/// Walk an `eth_getProof`-style Merkle-Patricia-trie proof from `root_hash`
+/// following `path` (the nibbles of the keccak'd key), verifying at every
+/// step that `keccak256(node_bytes) == expected_hash` before trusting its
+/// content. Branch nodes are 17-item RLP lists (16 nibble slots + a value
+/// slot); extension/leaf nodes are 2-item lists whose first element is a
+/// hex-prefix encoded path. Returns `Ok(None)` when the proof demonstrates
+/// the key is absent (a missing branch slot, or a mismatched leaf path).
+fn walk_mpt_proof(root_hash: [u8; 32], path: &[u8], proof: &[Vec<u8>]) -> Result<Option<Vec<u8>>, String> {
+    let mut expected_hash = root_hash;
+    let mut nibble_idx = 0usize;
+
+    for node_bytes in proof {
+        if keccak256(node_bytes) != expected_hash {
+            return Err("proof node hash does not match the expected trie root".to_string());
+        }
+
+        let rlp = Rlp::new(node_bytes);
+        let item_count = rlp.item_count().map_err(|e| e.to_string())?;
+
+        if item_count == 17 {
+            if nibble_idx >= path.len() {
+                let value = rlp.at(16).map_err(|e| e.to_string())?.data().map_err(|e| e.to_string())?.to_vec();
+                return Ok(if value.is_empty() { None } else { Some(value) });
+            }
+            let nibble = path[nibble_idx] as usize;
+            let child = rlp.at(nibble).map_err(|e| e.to_string())?.data().map_err(|e| e.to_string())?.to_vec();
+            if child.is_empty() {
+                return Ok(None);
+            }
+            if child.len() != 32 {
+                return Err("embedded (non-hashed) trie nodes are not supported".to_string());
             }
+            expected_hash.copy_from_slice(&child);
+            nibble_idx += 1;
+        } else if item_count == 2 {
+            let encoded_path = rlp.at(0).map_err(|e| e.to_string())?.data().map_err(|e| e.to_string())?.to_vec();
+            let (node_path, is_leaf) = hex_prefix_decode(&encoded_path);
+
+            if nibble_idx > path.len() {
+                return Err("proof consumed more of the key than it contains".to_string());
+            }
+            if !path[nibble_idx..].starts_with(node_path.as_slice()) {
+                return Ok(None);
+            }
+            nibble_idx += node_path.len();
+
+            let value_or_ref = rlp.at(1).map_err(|e| e.to_string())?.data().map_err(|e| e.to_string())?.to_vec();
+            if is_leaf {
+                return Ok(if nibble_idx == path.len() { Some(value_or_ref) } else { None });
+            }
+            if value_or_ref.len() != 32 {
+                return Err("embedded (non-hashed) trie nodes are not supported".to_string());
+            }
+            expected_hash.copy_from_slice(&value_or_ref);
+        } else {
+            return Err(format!("unexpected trie node with {} RLP items", item_count));
         }
     }
+
+    Err("proof ended before reaching a leaf".to_string())
+}
+
+/// Opens `endpoint_ws`, subscribes to `eth_subscribe("newHeads")`, and writes
+/// each new block's number into `latest_block` as it arrives. Runs for the
+/// lifetime of the process on its own thread; a dropped connection is not
+/// retried since a stale head just falls back to whatever was last written
+/// (or, before the first head arrives, to `OnChainConfig::block_number`).
+fn spawn_new_heads_tracker(endpoint_ws: String, latest_block: Arc<RwLock<Option<String>>>) {
+    std::thread::spawn(move || {
+        let (mut socket, _) = match tungstenite::connect(&endpoint_ws) {
+            Ok(conn) => conn,
+            Err(e) => {
+                println!("newHeads subscription failed to connect: {}", e);
+                return;
+            }
+        };
+        let subscribe = "{\"jsonrpc\":\"2.0\", \"method\": \"eth_subscribe\", \"params\": [\"newHeads\"], \"id\": 1}".to_string();
+        if let Err(e) = socket.send(tungstenite::Message::Text(subscribe)) {
+            println!("newHeads subscription failed to send: {}", e);
+            return;
+        }
+        loop {
+            let msg = match socket.read() {
+                Ok(tungstenite::Message::Text(msg)) => msg,
+                Ok(_) => continue,
+                Err(e) => {
+                    println!("newHeads subscription closed: {}", e);
+                    return;
+                }
+            };
+            let json: Value = match serde_json::from_str(&msg) {
+                Ok(json) => json,
+                Err(_) => continue,
+            };
+            // The subscription ack (`{"result": "0x..."}`) has no `params`;
+            // only notifications carry the new head.
+            if let Some(number) = json["params"]["result"]["number"].as_str() {
+                *latest_block.write().unwrap() = Some(number.to_string());
+            }
+        }
+    });
 }
 
 impl PriceOracle for OnChainConfig {
@@ -319,6 +993,183 @@ impl PriceOracle for OnChainConfig {
     }
 }
 
+/// A user-supplied, fully offline price source -- useful for tokens with no
+/// liquid market (test tokens, pre-launch tokens) or to pin a price for
+/// reproducible fuzzing.
+#[derive(Debug, Default)]
+pub struct StaticPriceOracle {
+    prices: HashMap<H160, (f64, u32)>,
+}
+
+impl StaticPriceOracle {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn set_price(&mut self, token: H160, price: f64, decimals: u32) {
+        self.prices.insert(token, (price, decimals));
+    }
+}
+
+impl PriceOracle for StaticPriceOracle {
+    fn fetch_token_price(&self, token_address: H160) -> Option<(f64, u32)> {
+        self.prices.get(&token_address).copied()
+    }
+}
+
+/// Reads a Uniswap-V2-shaped DEX pair's reserves directly out of storage
+/// (`get_contract_slot`, no ABI decoding needed) and derives the token's
+/// price from the reserve ratio, quoted in the pair's other token. That is
+/// a USD price only when `pair_address` pairs the token with a USD
+/// stablecoin; a quote against WETH is not converted to USD.
+/// Unlike an off-chain price API, this works at any historical
+/// `block_number` the `OnChainConfig` is pinned to.
+#[derive(Debug)]
+pub struct DexPriceOracle {
+    config: OnChainConfig,
+    pair_address: H160,
+}
+
+impl DexPriceOracle {
+    // Standard `UniswapV2Pair` storage layout: `token0`/`token1` are plain
+    // `address` state variables, and `reserve0`/`reserve1`/`blockTimestampLast`
+    // are packed into a single slot (112/112/32 bits).
+    const TOKEN0_SLOT: u64 = 6;
+    const TOKEN1_SLOT: u64 = 7;
+    const RESERVES_SLOT: u64 = 8;
+
+    pub fn new(config: OnChainConfig, pair_address: H160) -> Self {
+        Self { config, pair_address }
+    }
+
+    fn address_at_slot(&self, slot: u64) -> H160 {
+        let mut bytes = [0u8; 32];
+        self.config
+            .get_contract_slot(self.pair_address, U256::from(slot))
+            .to_big_endian(&mut bytes);
+        H160::from_slice(&bytes[12..])
+    }
+
+    fn reserves(&self) -> Option<(U256, U256)> {
+        let packed = self.config.get_contract_slot(self.pair_address, U256::from(Self::RESERVES_SLOT));
+        let mask_112 = (U256::from(1u64) << 112) - U256::from(1u64);
+        let reserve0 = packed & mask_112;
+        let reserve1 = (packed >> 112) & mask_112;
+        Some((reserve0, reserve1))
+    }
+
+    /// Calls the ERC20 `decimals() -> uint8` view function (selector
+    /// `0x313ce567`) on `token`. Defaults to 18 if the call fails or the
+    /// token doesn't implement it, which matches the common case and keeps
+    /// this a best-effort lookup rather than a hard failure.
+    fn decimals(&self, token: H160) -> u32 {
+        let params = format!(
+            "[{{\"to\": \"0x{}\", \"data\": \"0x313ce567\"}}, \"{}\"]",
+            hex::encode(token),
+            self.config.current_block_number()
+        );
+        self.config
+            ._request("eth_call".to_string(), params)
+            .and_then(|result| result.as_str().map(|s| s.trim_start_matches("0x").to_string()))
+            .and_then(|hex_str| u64::from_str_radix(&hex_str, 16).ok())
+            .map(|v| v as u32)
+            .unwrap_or(18)
+    }
+}
+
+impl PriceOracle for DexPriceOracle {
+    fn fetch_token_price(&self, token_address: H160) -> Option<(f64, u32)> {
+        let (reserve0, reserve1) = self.reserves()?;
+        let token0 = self.address_at_slot(Self::TOKEN0_SLOT);
+        let token1 = self.address_at_slot(Self::TOKEN1_SLOT);
+
+        let (token_reserve, reference_reserve, reference_token) = if token0 == token_address {
+            (reserve0, reserve1, token1)
+        } else if token1 == token_address {
+            (reserve1, reserve0, token0)
+        } else {
+            return None;
+        };
+
+        if token_reserve.is_zero() {
+            return None;
+        }
+
+        // Reserves are raw on-chain integers, so a pair between tokens with
+        // different `decimals()` (e.g. USDC/USDT at 6 vs WETH at 18) needs
+        // both sides rescaled to the same unit before dividing, or the
+        // result is off by orders of magnitude.
+        let token_decimals = self.decimals(token_address);
+        let reference_decimals = self.decimals(reference_token);
+        let token_reserve_scaled = token_reserve.as_u128() as f64 / 10f64.powi(token_decimals as i32);
+        let reference_reserve_scaled = reference_reserve.as_u128() as f64 / 10f64.powi(reference_decimals as i32);
+        let price = reference_reserve_scaled / token_reserve_scaled;
+        Some((price, 18))
+    }
+}
+
+/// Aggregates several `PriceOracle` backends and returns their median,
+/// so a single missing/rate-limited source (e.g. Moralis) doesn't silently
+/// turn into `None` and break value-based bug oracles. Caches per-token for
+/// the lifetime of the instance; the cache is not keyed by block, so a
+/// cached quote is still returned after an `OnChainConfig` that follows the
+/// head (see `with_endpoint_ws`) has moved to a newer block.
+#[derive(Debug)]
+pub struct CompositePriceOracle {
+    sources: Vec<Box<dyn PriceOracle>>,
+    cache: RefCell<HashMap<H160, (f64, u32)>>,
+}
+
+impl CompositePriceOracle {
+    pub fn new(sources: Vec<Box<dyn PriceOracle>>) -> Self {
+        Self {
+            sources,
+            cache: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn add_source(&mut self, source: Box<dyn PriceOracle>) {
+        self.sources.push(source);
+    }
+}
+
+impl PriceOracle for CompositePriceOracle {
+    fn fetch_token_price(&self, token_address: H160) -> Option<(f64, u32)> {
+        if let Some(cached) = self.cache.borrow().get(&token_address) {
+            return Some(*cached);
+        }
+
+        // Normalize every quote to an implicit 18-decimals so sources that
+        // report different `decimals` are comparable before taking a median.
+        let mut normalized: Vec<f64> = self
+            .sources
+            .iter()
+            .filter_map(|source| source.fetch_token_price(token_address))
+            .map(|(price, decimals)| price * 10f64.powi(18 - decimals as i32))
+            .collect();
+        if normalized.is_empty() {
+            return None;
+        }
+        normalized.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        // Drop obvious outliers (3x or more away from the raw median)
+        // before taking the final median.
+        let raw_median = normalized[normalized.len() / 2];
+        let mut filtered: Vec<f64> = normalized
+            .into_iter()
+            .filter(|p| *p > raw_median / 3.0 && *p < raw_median * 3.0)
+            .collect();
+        if filtered.is_empty() {
+            filtered.push(raw_median);
+        }
+        let median = filtered[filtered.len() / 2];
+
+        let result = (median, 18);
+        self.cache.borrow_mut().insert(token_address, result);
+        Some(result)
+    }
+}
+
 mod tests {
     use super::*;
     use crate::onchain::endpoints::Chain::BSC;
@@ -371,4 +1222,153 @@ mod tests {
         );
         println!("{:?}", v)
     }
+
+    #[test]
+    fn test_composite_price_oracle_median_and_outliers() {
+        let token = H160::from_str("0xa0a2ee912caf7921eaabc866c6ef6fec8f7e90a4").unwrap();
+
+        let mut agree_low = StaticPriceOracle::new();
+        agree_low.set_price(token, 1.0, 18);
+        let mut agree_high = StaticPriceOracle::new();
+        agree_high.set_price(token, 1.1, 18);
+        let mut outlier = StaticPriceOracle::new();
+        outlier.set_price(token, 1000.0, 18);
+
+        let composite = CompositePriceOracle::new(vec![
+            Box::new(agree_low),
+            Box::new(agree_high),
+            Box::new(outlier),
+        ]);
+
+        let (price, decimals) = composite.fetch_token_price(token).unwrap();
+        assert_eq!(decimals, 18);
+        assert!(price < 2.0, "outlier should have been filtered out: {}", price);
+
+        // cached on second call
+        let (cached_price, _) = composite.fetch_token_price(token).unwrap();
+        assert_eq!(price, cached_price);
+    }
+
+    #[test]
+    fn test_fetch_abi_offline_registry_takes_priority() {
+        let config = OnChainConfig::new(BSC, 0);
+        let address = H160::from_str("0xa0a2ee912caf7921eaabc866c6ef6fec8f7e90a4").unwrap();
+        config.add_abi(address, "[{\"type\":\"function\"}]".to_string());
+        assert_eq!(config.fetch_abi(address), Some("[{\"type\":\"function\"}]".to_string()));
+    }
+
+    #[test]
+    fn test_current_block_number_prefers_tracked_head() {
+        let config = OnChainConfig::new(BSC, 0);
+        assert_eq!(config.block_number, "latest");
+        assert_eq!(config.current_block_number(), "latest");
+
+        *config.tracked_latest_block.write().unwrap() = Some("0x64".to_string());
+        assert_eq!(config.current_block_number(), "0x64");
+    }
+
+    /// Mirrors `hex_prefix_decode`'s bit layout, so these fixtures can build
+    /// leaf/extension node paths by hand without going through a real trie.
+    fn encode_hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+        let is_odd = nibbles.len() % 2 == 1;
+        let flag: u8 = (if is_leaf { 0x20 } else { 0x00 }) | (if is_odd { 0x10 } else { 0x00 });
+
+        let mut iter = nibbles.iter();
+        let mut out: Vec<u8> = Vec::new();
+        out.push(if is_odd {
+            flag | *iter.next().unwrap()
+        } else {
+            flag
+        });
+        while let Some(&hi) = iter.next() {
+            let lo = *iter.next().unwrap();
+            out.push((hi << 4) | lo);
+        }
+        out
+    }
+
+    fn rlp_node(items: &[&[u8]]) -> Vec<u8> {
+        let mut stream = rlp::RlpStream::new_list(items.len());
+        for item in items {
+            stream.append(*item);
+        }
+        stream.out().to_vec()
+    }
+
+    #[test]
+    fn test_walk_mpt_proof_leaf_node() {
+        let path = vec![0xa, 0xb, 0xc];
+        let value = b"hello-leaf".to_vec();
+        let encoded_path = encode_hex_prefix(&path, true);
+        let leaf = rlp_node(&[&encoded_path, &value]);
+        let root_hash = keccak256(&leaf);
+
+        let result = walk_mpt_proof(root_hash, &path, &[leaf]).unwrap();
+        assert_eq!(result, Some(value));
+    }
+
+    #[test]
+    fn test_walk_mpt_proof_missing_key() {
+        let path = vec![0xa, 0xb, 0xc];
+        let value = b"hello-leaf".to_vec();
+        let encoded_path = encode_hex_prefix(&path, true);
+        let leaf = rlp_node(&[&encoded_path, &value]);
+        let root_hash = keccak256(&leaf);
+
+        // Same leaf, but queried with a path that diverges on the last nibble.
+        let other_path = vec![0xa, 0xb, 0xf];
+        let result = walk_mpt_proof(root_hash, &other_path, &[leaf]).unwrap();
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_walk_mpt_proof_branch_node() {
+        let leaf_path = vec![0x1, 0x2];
+        let leaf_value = b"leaf-via-branch".to_vec();
+        let leaf_encoded_path = encode_hex_prefix(&leaf_path, true);
+        let leaf = rlp_node(&[&leaf_encoded_path, &leaf_value]);
+        let leaf_hash = keccak256(&leaf);
+
+        let mut branch_items: Vec<Vec<u8>> = vec![vec![]; 17];
+        branch_items[0x5] = leaf_hash.to_vec();
+        let branch_refs: Vec<&[u8]> = branch_items.iter().map(|v| v.as_slice()).collect();
+        let branch = rlp_node(&branch_refs);
+        let root_hash = keccak256(&branch);
+
+        let full_path = vec![0x5, 0x1, 0x2];
+        let result = walk_mpt_proof(root_hash, &full_path, &[branch, leaf]).unwrap();
+        assert_eq!(result, Some(leaf_value));
+    }
+
+    #[test]
+    fn test_walk_mpt_proof_extension_node() {
+        let ext_path = vec![0x7, 0x8];
+        let branch_value = b"value-at-branch".to_vec();
+
+        let mut branch_items: Vec<Vec<u8>> = vec![vec![]; 17];
+        branch_items[16] = branch_value.clone();
+        let branch_refs: Vec<&[u8]> = branch_items.iter().map(|v| v.as_slice()).collect();
+        let branch = rlp_node(&branch_refs);
+        let branch_hash = keccak256(&branch);
+
+        let ext_encoded_path = encode_hex_prefix(&ext_path, false);
+        let extension = rlp_node(&[&ext_encoded_path[..], &branch_hash[..]]);
+        let root_hash = keccak256(&extension);
+
+        let result = walk_mpt_proof(root_hash, &ext_path, &[extension, branch]).unwrap();
+        assert_eq!(result, Some(branch_value));
+    }
+
+    #[test]
+    fn test_walk_mpt_proof_root_hash_mismatch_is_rejected() {
+        let path = vec![0xa, 0xb, 0xc];
+        let value = b"hello-leaf".to_vec();
+        let encoded_path = encode_hex_prefix(&path, true);
+        let leaf = rlp_node(&[&encoded_path, &value]);
+
+        // A proof node that doesn't hash to the claimed root must be rejected
+        // outright, rather than trusted and walked.
+        let wrong_root = [0u8; 32];
+        assert!(walk_mpt_proof(wrong_root, &path, &[leaf]).is_err());
+    }
 }